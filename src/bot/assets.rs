@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serenity::prelude::TypeMapKey;
+use tokio::sync::RwLock;
+
+/// Error returned by `AssetRegistry` operations
+#[derive(Debug)]
+pub enum AssetError {
+	/// the registry already holds `max_assets` entries
+	CapacityReached(usize),
+	/// an asset with that name is already registered
+	AlreadyExists(String),
+	/// requested asset isn't in the index
+	NotFound(String),
+	/// `name` isn't a bare file name (e.g. contains a path separator, `..`,
+	/// or is absolute), and so can't be trusted to stay inside the assets
+	/// directory
+	InvalidName(String),
+	/// underlying filesystem operation failed
+	Io(io::Error),
+}
+
+impl fmt::Display for AssetError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AssetError::CapacityReached(max) => write!(f, "asset registry is full (max {} assets)", max),
+			AssetError::AlreadyExists(name) => write!(f, "an asset named '{}' already exists", name),
+			AssetError::NotFound(name) => write!(f, "no asset named '{}'", name),
+			AssetError::InvalidName(name) => write!(f, "'{}' is not a valid asset name", name),
+			AssetError::Io(err) => write!(f, "asset storage error: {}", err),
+		}
+	}
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<io::Error> for AssetError {
+	fn from(err: io::Error) -> Self {
+		AssetError::Io(err)
+	}
+}
+
+/// Returns whether `name` is a bare file name with no path components, so it
+/// can't escape the assets directory (`../x`) or replace an unrelated
+/// absolute path (`/etc/passwd`) when joined onto it.
+fn is_safe_asset_name(name: &str) -> bool {
+	!name.is_empty() && Path::new(name).file_name() == Some(OsStr::new(name))
+}
+
+/// Indexes the assets available under a directory and enforces a maximum
+/// count, so operators can cap disk usage instead of relying on an
+/// unchecked directory of uploads.
+pub struct AssetRegistry {
+	directory: PathBuf,
+	max_assets: usize,
+	index: HashMap<String, PathBuf>,
+}
+
+impl AssetRegistry {
+	/// Scans `directory` and indexes every file found there by its full file
+	/// name, the same key `register_asset` uses, so a name round-trips
+	/// identically across a restart. `max_assets` caps how many entries
+	/// `register_asset` will accept afterwards.
+	pub fn scan(directory: impl Into<PathBuf>, max_assets: usize) -> io::Result<Self> {
+		let directory = directory.into();
+		let mut index = HashMap::new();
+
+		if directory.exists() {
+			for entry in fs::read_dir(&directory)? {
+				let entry = entry?;
+				let path = entry.path();
+				if !path.is_file() {
+					continue;
+				}
+				if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+					index.insert(String::from(name), path);
+				}
+			}
+		}
+
+		Ok(Self {
+			directory,
+			max_assets,
+			index,
+		})
+	}
+
+	/// Writes `bytes` to disk under `name` and indexes it, rejecting the
+	/// asset if the registry is already at `max_assets` or `name` is taken.
+	pub fn register_asset(&mut self, name: &str, bytes: &[u8]) -> Result<(), AssetError> {
+		if !is_safe_asset_name(name) {
+			return Err(AssetError::InvalidName(String::from(name)));
+		}
+		if self.index.contains_key(name) {
+			return Err(AssetError::AlreadyExists(String::from(name)));
+		}
+		if self.index.len() >= self.max_assets {
+			return Err(AssetError::CapacityReached(self.max_assets));
+		}
+
+		fs::create_dir_all(&self.directory)?;
+		let path = self.directory.join(name);
+		fs::write(&path, bytes)?;
+		self.index.insert(String::from(name), path);
+		Ok(())
+	}
+
+	/// Reads the bytes of the asset registered under `name`
+	pub fn get_asset(&self, name: &str) -> Result<Vec<u8>, AssetError> {
+		let path = self.index.get(name).ok_or_else(|| AssetError::NotFound(String::from(name)))?;
+		Ok(fs::read(path)?)
+	}
+
+	/// Removes the asset registered under `name`, from both disk and the index
+	pub fn remove_asset(&mut self, name: &str) -> Result<(), AssetError> {
+		let path = self.index.get(name).ok_or_else(|| AssetError::NotFound(String::from(name)))?.clone();
+		fs::remove_file(path)?;
+		self.index.remove(name);
+		Ok(())
+	}
+
+	/// Lists the names of every currently registered asset
+	pub fn list_assets(&self) -> Vec<&str> {
+		self.index.keys().map(String::as_str).collect()
+	}
+
+	/// Directory this registry scans and writes assets under
+	pub fn directory(&self) -> &Path {
+		&self.directory
+	}
+}
+
+pub struct AssetRegistryStore;
+
+impl TypeMapKey for AssetRegistryStore {
+	type Value = Arc<RwLock<AssetRegistry>>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn temp_dir(label: &str) -> PathBuf {
+		std::env::temp_dir().join(format!("rusty-bot-asset-tests-{}-{}", std::process::id(), label))
+	}
+
+	#[test]
+	fn rejects_names_that_escape_the_assets_directory() {
+		let dir = temp_dir("traversal");
+		let mut registry = AssetRegistry::scan(&dir, 10).unwrap();
+
+		assert!(matches!(registry.register_asset("../../etc/cron.d/x", b"x"), Err(AssetError::InvalidName(_))));
+		assert!(matches!(registry.register_asset("/etc/passwd", b"x"), Err(AssetError::InvalidName(_))));
+		assert!(matches!(registry.register_asset("a/b", b"x"), Err(AssetError::InvalidName(_))));
+		assert!(registry.list_assets().is_empty());
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn asset_key_survives_a_rescan() {
+		let dir = temp_dir("rescan");
+		let mut registry = AssetRegistry::scan(&dir, 10).unwrap();
+		registry.register_asset("cat.png", b"meow").unwrap();
+		assert!(registry.get_asset("cat.png").is_ok());
+
+		// Simulates a restart: the on-disk file is the only source of truth.
+		let rescanned = AssetRegistry::scan(&dir, 10).unwrap();
+		assert!(rescanned.get_asset("cat.png").is_ok());
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn register_get_and_remove_round_trip() {
+		let dir = temp_dir("round-trip");
+		let mut registry = AssetRegistry::scan(&dir, 10).unwrap();
+
+		registry.register_asset("cat.png", b"meow").unwrap();
+		assert_eq!(registry.get_asset("cat.png").unwrap(), b"meow");
+		assert_eq!(registry.list_assets(), vec!["cat.png"]);
+
+		registry.remove_asset("cat.png").unwrap();
+		assert!(matches!(registry.get_asset("cat.png"), Err(AssetError::NotFound(_))));
+		assert!(registry.list_assets().is_empty());
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn rejects_new_assets_once_at_capacity() {
+		let dir = temp_dir("capacity");
+		let mut registry = AssetRegistry::scan(&dir, 1).unwrap();
+
+		registry.register_asset("cat.png", b"meow").unwrap();
+		assert!(matches!(registry.register_asset("dog.png", b"woof"), Err(AssetError::CapacityReached(1))));
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+}
@@ -0,0 +1,274 @@
+use serenity::client::Context;
+use serenity::framework::standard::macros::{command, group};
+use serenity::framework::standard::{Args, CommandResult};
+use serenity::model::channel::Message;
+use serenity::model::id::GuildId;
+
+use super::assets::AssetRegistryStore;
+use super::config::ConfigStore;
+use super::permissions::{is_authorized, UNAUTHORIZED_MESSAGE};
+use super::worker::{WorkerManagerStore, WorkerStatus};
+
+/// Checks whether the member who sent `msg` in `guild_id` has the permission
+/// configured (per-guild) for `command` (see `Configuration::get_required_permission`).
+async fn is_member_authorized(ctx: &Context, msg: &Message, guild_id: GuildId, command: &str) -> serenity::Result<bool> {
+	let member = guild_id.member(ctx, msg.author.id).await?;
+	let member_permissions = member.permissions(ctx)?;
+
+	let data = ctx.data.read().await;
+	let store = data.get::<ConfigStore>().expect("ConfigStore not inserted").clone();
+	drop(data);
+
+	let mut configs = store.write().await;
+	let config = configs.get_or_default(guild_id).map_err(serenity::Error::Io)?;
+	Ok(is_authorized(config, command, member_permissions))
+}
+
+/// Reports every worker currently known to the `WorkerManager`, and whether
+/// each is active, idle, or dead.
+#[command]
+pub async fn workers(ctx: &Context, msg: &Message) -> CommandResult {
+	let data = ctx.data.read().await;
+	let manager = data.get::<WorkerManagerStore>().expect("WorkerManagerStore not inserted").clone();
+	drop(data);
+
+	let statuses = manager.status().await;
+	if statuses.is_empty() {
+		msg.reply(ctx, "No workers are currently registered.").await?;
+		return Ok(());
+	}
+
+	let report = statuses
+		.iter()
+		.map(|info| {
+			let status = match info.status() {
+				WorkerStatus::Active => "active",
+				WorkerStatus::Idle => "idle",
+				WorkerStatus::Dead => "dead",
+			};
+			format!("- {}: {}", info.name(), status)
+		})
+		.collect::<Vec<_>>()
+		.join("\n");
+
+	msg.reply(ctx, report).await?;
+	Ok(())
+}
+
+/// Mutes/unmutes the bot in this guild. Requires `MUTE_MEMBERS` by default
+/// (see `Configuration::get_required_permission`).
+#[command]
+pub async fn mute(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "mute").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let new_value = args.single::<bool>().unwrap_or(true);
+
+	let data = ctx.data.read().await;
+	let store = data.get::<ConfigStore>().expect("ConfigStore not inserted").clone();
+	drop(data);
+
+	store.write().await.with_mut(guild_id, |config| config.mute(new_value))?;
+
+	msg.reply(ctx, format!("Muted: {}", new_value)).await?;
+	Ok(())
+}
+
+/// Configures whether command calls are cleared after being run. Requires
+/// `MANAGE_MESSAGES` by default.
+#[command]
+pub async fn clear_calls(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "clear_calls").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let new_value = args.single::<bool>().unwrap_or(true);
+
+	let data = ctx.data.read().await;
+	let store = data.get::<ConfigStore>().expect("ConfigStore not inserted").clone();
+	drop(data);
+
+	store.write().await.with_mut(guild_id, |config| config.clear_calls(new_value))?;
+
+	msg.reply(ctx, format!("Clear calls: {}", new_value)).await?;
+	Ok(())
+}
+
+/// Configures the flood command's delay. Requires `MANAGE_MESSAGES` by default.
+#[command]
+pub async fn flood_delay(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "flood_delay").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let new_value = args.single::<f32>()?;
+
+	let data = ctx.data.read().await;
+	let store = data.get::<ConfigStore>().expect("ConfigStore not inserted").clone();
+	drop(data);
+
+	store.write().await.with_mut(guild_id, |config| config.set_flood_delay(new_value))?;
+
+	msg.reply(ctx, format!("Flood delay: {}", new_value)).await?;
+	Ok(())
+}
+
+/// Configures the poomp command's delay. Requires `MANAGE_MESSAGES` by default.
+#[command]
+pub async fn poomp_delay(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "poomp_delay").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let new_value = args.single::<f32>()?;
+
+	let data = ctx.data.read().await;
+	let store = data.get::<ConfigStore>().expect("ConfigStore not inserted").clone();
+	drop(data);
+
+	store.write().await.with_mut(guild_id, |config| config.set_poomp_delay(new_value))?;
+
+	msg.reply(ctx, format!("Poomp delay: {}", new_value)).await?;
+	Ok(())
+}
+
+/// Configures the assets storage directory. Requires `MANAGE_GUILD` by default.
+#[command]
+pub async fn assets_dir(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "assets_directory_path").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let new_value = args.single::<String>()?;
+
+	let data = ctx.data.read().await;
+	let store = data.get::<ConfigStore>().expect("ConfigStore not inserted").clone();
+	drop(data);
+
+	store.write().await.with_mut(guild_id, |config| config.set_assets_dir(&new_value))?;
+
+	msg.reply(ctx, format!("Assets directory: {}", new_value)).await?;
+	Ok(())
+}
+
+/// Registers the message's first attachment as an asset named by the first
+/// argument. Requires `MANAGE_GUILD` by default.
+#[command]
+pub async fn asset_add(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "asset_add").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let name = args.single::<String>()?;
+	let attachment = match msg.attachments.first() {
+		Some(attachment) => attachment,
+		None => {
+			msg.reply(ctx, "Attach the file you want to register as an asset.").await?;
+			return Ok(());
+		}
+	};
+	let bytes = attachment.download().await?;
+
+	let data = ctx.data.read().await;
+	let registry = data.get::<AssetRegistryStore>().expect("AssetRegistryStore not inserted").clone();
+	drop(data);
+
+	match registry.write().await.register_asset(&name, &bytes) {
+		Ok(()) => {
+			msg.reply(ctx, format!("Registered asset '{}'.", name)).await?;
+		}
+		Err(err) => {
+			msg.reply(ctx, format!("Could not register asset: {}", err)).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Lists every asset currently registered in the bot's `AssetRegistry`, which
+/// is shared by all guilds.
+#[command]
+pub async fn asset_list(ctx: &Context, msg: &Message) -> CommandResult {
+	let data = ctx.data.read().await;
+	let registry = data.get::<AssetRegistryStore>().expect("AssetRegistryStore not inserted").clone();
+	drop(data);
+
+	let registry = registry.read().await;
+	let names = registry.list_assets();
+	if names.is_empty() {
+		msg.reply(ctx, "No assets registered.").await?;
+	} else {
+		msg.reply(ctx, names.join(", ")).await?;
+	}
+	Ok(())
+}
+
+/// Removes the asset named by the first argument. Requires `MANAGE_GUILD` by default.
+#[command]
+pub async fn asset_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+	let guild_id = match msg.guild_id {
+		Some(guild_id) => guild_id,
+		None => return Ok(()),
+	};
+
+	if !is_member_authorized(ctx, msg, guild_id, "asset_remove").await? {
+		msg.reply(ctx, UNAUTHORIZED_MESSAGE).await?;
+		return Ok(());
+	}
+
+	let name = args.single::<String>()?;
+
+	let data = ctx.data.read().await;
+	let registry = data.get::<AssetRegistryStore>().expect("AssetRegistryStore not inserted").clone();
+	drop(data);
+
+	match registry.write().await.remove_asset(&name) {
+		Ok(()) => {
+			msg.reply(ctx, format!("Removed asset '{}'.", name)).await?;
+		}
+		Err(err) => {
+			msg.reply(ctx, format!("Could not remove asset: {}", err)).await?;
+		}
+	}
+	Ok(())
+}
+
+#[group]
+#[commands(workers, mute, clear_calls, flood_delay, poomp_delay, assets_dir, asset_add, asset_list, asset_remove)]
+pub struct BotCommands;
@@ -1,5 +1,29 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::Permissions;
+
 /// default location for assets storage
 const DEFAULT_ASSETS_DIR: &str = "assets";
+/// default cap on how many assets an `AssetRegistry` will hold
+const DEFAULT_MAX_ASSETS: usize = 100;
+
+/// minimum permission required to run a command, keyed by command name, used
+/// to seed `Configuration::new()`
+fn default_required_permission() -> HashMap<String, Permissions> {
+	let mut permissions = HashMap::new();
+	permissions.insert(String::from("mute"), Permissions::MUTE_MEMBERS);
+	permissions.insert(String::from("clear_calls"), Permissions::MANAGE_MESSAGES);
+	permissions.insert(String::from("flood_delay"), Permissions::MANAGE_MESSAGES);
+	permissions.insert(String::from("poomp_delay"), Permissions::MANAGE_MESSAGES);
+	permissions.insert(String::from("assets_directory_path"), Permissions::MANAGE_GUILD);
+	permissions.insert(String::from("asset_add"), Permissions::MANAGE_GUILD);
+	permissions.insert(String::from("asset_remove"), Permissions::MANAGE_GUILD);
+	permissions
+}
 
 
 /// Represents / contains bot settings/configuration
@@ -18,7 +42,15 @@ const DEFAULT_ASSETS_DIR: &str = "assets";
 /// poomp_delay: 		max delay to wait before each `$poomp` call, if the delay is passed the bot leaves the channel
 /// 
 /// assets_directory_path: 	the assets storage location
+///
+/// required_permission: 	minimum Discord permission a member needs to run a given
+/// 					(state-changing) command, keyed by command name
+///
+/// max_assets: 		maximum number of assets the `AssetRegistry` will hold before
+/// 					rejecting new ones
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Configuration {
 	clear_calls: bool,
 	muted: bool,
@@ -26,6 +58,8 @@ pub struct Configuration {
 	maintained: bool,
 	poomp_delay: f32,
 	assets_directory_path: String,
+	required_permission: HashMap<String, Permissions>,
+	max_assets: usize,
 }
 
 impl Configuration {
@@ -37,6 +71,8 @@ impl Configuration {
 			maintained: false,
 			poomp_delay: 0.0,
 			assets_directory_path: String::from(DEFAULT_ASSETS_DIR),
+			required_permission: default_required_permission(),
+			max_assets: DEFAULT_MAX_ASSETS,
 		}
 	}
 	/// Returns if the commands calls are cleared
@@ -91,6 +127,79 @@ impl Configuration {
 	pub fn set_assets_dir(&mut self, new_value: &str) {
 		self.assets_directory_path = String::from(new_value);
 	}
+
+	/// Returns the minimum permission required to run `command`, or an empty
+	/// set of permissions if the command isn't gated.
+	pub fn get_required_permission(&self, command: &str) -> Permissions {
+		self.required_permission.get(command).copied().unwrap_or_else(Permissions::empty)
+	}
+	/// Configures the minimum permission required to run `command`
+	pub fn set_required_permission(&mut self, command: &str, permission: Permissions) {
+		self.required_permission.insert(String::from(command), permission);
+	}
+
+	/// Returns the maximum number of assets the `AssetRegistry` will hold
+	pub fn get_max_assets(&self) -> usize {
+		self.max_assets
+	}
+	/// Sets the maximum number of assets the `AssetRegistry` will hold
+	pub fn set_max_assets(&mut self, new_value: usize) {
+		self.max_assets = new_value;
+	}
+
+	/// Loads a `Configuration` from `path`. An absent file is treated as
+	/// "use defaults" so a fresh install doesn't need to pre-seed anything;
+	/// keys missing from an older file fall back to `Configuration::new()`
+	/// as well, so the format can grow without breaking existing installs.
+	pub fn load_from(path: impl AsRef<Path>) -> io::Result<Self> {
+		let path = path.as_ref();
+		if !path.exists() {
+			return Ok(Self::new());
+		}
+		let contents = fs::read_to_string(path)?;
+		toml::from_str(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+	}
+
+	/// Persists this `Configuration` to `path` as TOML, creating the file if
+	/// it does not exist yet. Called after every mutating setter reached
+	/// through a command, so edits survive restarts.
+	pub fn save_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		let serialized = toml::to_string(self).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+		fs::write(path, serialized)
+	}
+}
+
+#[cfg(test)]
+mod persistence_tests {
+	use super::*;
+
+	fn temp_path(label: &str) -> std::path::PathBuf {
+		std::env::temp_dir().join(format!("rusty-bot-config-tests-{}-{}.toml", std::process::id(), label))
+	}
+
+	#[test]
+	fn load_from_an_absent_file_uses_defaults() {
+		let path = temp_path("absent");
+		let _ = fs::remove_file(&path);
+
+		assert_eq!(Configuration::load_from(&path).unwrap(), Configuration::new());
+	}
+
+	#[test]
+	fn save_then_load_round_trips() {
+		let path = temp_path("round-trip");
+		let mut config = Configuration::new();
+		config.mute(true);
+		config.set_flood_delay(1.5);
+		config.set_assets_dir("custom_assets");
+		config.set_max_assets(42);
+
+		config.save_to(&path).unwrap();
+		let loaded = Configuration::load_from(&path).unwrap();
+
+		assert_eq!(config, loaded);
+		let _ = fs::remove_file(&path);
+	}
 }
 
 impl PartialEq for Configuration {
@@ -100,7 +209,9 @@ impl PartialEq for Configuration {
 		self.flood_delay == other.get_flood_delay() &&
 		self.maintained == other.get_maintained() &&
 		self.poomp_delay == other.get_poomp_delay() &&
-		self.assets_directory_path == other.get_assets_dir()
+		self.assets_directory_path == other.get_assets_dir() &&
+		self.required_permission == other.required_permission &&
+		self.max_assets == other.get_max_assets()
 	}
 }
 
@@ -117,7 +228,9 @@ pub struct ConfigBuilder {
 	flood_delay: Option<f32>,
 	maintained: Option<bool>,
 	poomp_delay: Option<f32>,
-	assets_directory_path: Option<String>
+	assets_directory_path: Option<String>,
+	required_permission: HashMap<String, Permissions>,
+	max_assets: Option<usize>,
 }
 
 impl ConfigBuilder {
@@ -129,9 +242,39 @@ impl ConfigBuilder {
 			flood_delay: None,
 			maintained: None,
 			poomp_delay: None,
-			assets_directory_path: None
+			assets_directory_path: None,
+			required_permission: HashMap::new(),
+			max_assets: None,
 		}
 	}
+	/// Seeds a new builder from an on-disk config file, so callers can layer
+	/// additional overrides on top of the persisted state. An absent file is
+	/// treated the same as "use defaults" (see `Configuration::load_from`).
+	pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+		let config = Configuration::load_from(path)?;
+		Ok(Self {
+			clear_command_calls: Some(config.get_clear_calls()),
+			mute_bot: Some(config.muted()),
+			flood_delay: Some(config.get_flood_delay()),
+			maintained: Some(config.get_maintained()),
+			poomp_delay: Some(config.get_poomp_delay()),
+			assets_directory_path: Some(config.get_assets_dir()),
+			required_permission: config.required_permission.clone(),
+			max_assets: Some(config.get_max_assets()),
+		})
+	}
+	#[allow(dead_code)]
+	/// Configures the maximum number of assets the `AssetRegistry` will hold
+	pub fn max_assets(&mut self, new_value: Option<usize>) -> &mut Self {
+		self.max_assets = new_value;
+		self
+	}
+	#[allow(dead_code)]
+	/// Overrides the minimum permission required to run `command`
+	pub fn required_permission(&mut self, command: &str, permission: Permissions) -> &mut Self {
+		self.required_permission.insert(String::from(command), permission);
+		self
+	}
 	#[allow(dead_code)]
 	/// Configure whether the command calls should be clear or not
 	pub fn clear_calls(&mut self, new_value: bool) -> &mut Self{
@@ -188,15 +331,117 @@ impl ConfigBuilder {
 		if let Some(strpath) = &self.assets_directory_path {
 			new_conf.set_assets_dir(strpath);
 		}
+		for (command, permission) in &self.required_permission {
+			new_conf.set_required_permission(command, *permission);
+		}
+		if let Some(max_assets) = self.max_assets {
+			new_conf.set_max_assets(max_assets);
+		}
 		new_conf
 	}
 }
 
+use serenity::model::id::GuildId;
 use serenity::prelude::TypeMapKey;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+
+/// Holds one `Configuration` per guild, so muting or changing the assets
+/// directory in one server does not leak into another. Each guild's
+/// configuration is loaded from (and persisted to) its own file under
+/// `directory`, named after the guild's id.
+pub struct GuildConfigs {
+	directory: String,
+	configs: HashMap<GuildId, Configuration>,
+}
+
+impl GuildConfigs {
+	pub fn new(directory: impl Into<String>) -> Self {
+		Self {
+			directory: directory.into(),
+			configs: HashMap::new(),
+		}
+	}
+
+	fn config_path(&self, guild_id: GuildId) -> String {
+		format!("{}/{}.toml", self.directory, guild_id.0)
+	}
+
+	/// Returns the guild's configuration, loading it from disk (or falling
+	/// back to `Configuration::new()`, see `Configuration::load_from`) the
+	/// first time it's requested. A load failure that isn't "the file
+	/// doesn't exist yet" (a corrupt file, a permissions error, ...) is
+	/// propagated rather than silently replaced with defaults, since
+	/// `with_mut` would otherwise overwrite the guild's real settings with
+	/// those defaults on its next save.
+	pub fn get_or_default(&mut self, guild_id: GuildId) -> io::Result<&Configuration> {
+		if !self.configs.contains_key(&guild_id) {
+			let config = Configuration::load_from(self.config_path(guild_id))?;
+			self.configs.insert(guild_id, config);
+		}
+		Ok(self.configs.get(&guild_id).expect("just inserted"))
+	}
+
+	/// Mutates the guild's configuration in place via `f`, then persists the
+	/// result to disk so the edit survives restarts.
+	pub fn with_mut<F>(&mut self, guild_id: GuildId, f: F) -> io::Result<()>
+	where
+		F: FnOnce(&mut Configuration),
+	{
+		self.get_or_default(guild_id)?;
+		let config = self.configs.get_mut(&guild_id).expect("just inserted");
+		f(config);
+		fs::create_dir_all(&self.directory)?;
+		config.save_to(self.config_path(guild_id))
+	}
+}
+
 pub struct ConfigStore;
 
 impl TypeMapKey for ConfigStore {
-    type Value = Arc<RwLock<Configuration>>;
+    type Value = Arc<RwLock<GuildConfigs>>;
+}
+
+#[cfg(test)]
+mod guild_configs_tests {
+	use super::*;
+
+	fn temp_dir(label: &str) -> String {
+		std::env::temp_dir()
+			.join(format!("rusty-bot-guild-config-tests-{}-{}", std::process::id(), label))
+			.to_str()
+			.unwrap()
+			.to_string()
+	}
+
+	#[test]
+	fn settings_in_one_guild_do_not_leak_into_another() {
+		let dir = temp_dir("isolation");
+		let mut configs = GuildConfigs::new(&dir);
+		let guild_a = GuildId(1);
+		let guild_b = GuildId(2);
+
+		configs.with_mut(guild_a, |config| config.mute(true)).unwrap();
+
+		assert!(configs.get_or_default(guild_a).unwrap().muted());
+		assert!(!configs.get_or_default(guild_b).unwrap().muted());
+
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn mutations_survive_a_fresh_guild_configs_instance() {
+		let dir = temp_dir("persistence");
+		let guild_id = GuildId(42);
+
+		{
+			let mut configs = GuildConfigs::new(&dir);
+			configs.with_mut(guild_id, |config| config.set_flood_delay(2.5)).unwrap();
+		}
+
+		let mut reloaded = GuildConfigs::new(&dir);
+		assert_eq!(reloaded.get_or_default(guild_id).unwrap().get_flood_delay(), 2.5);
+
+		let _ = fs::remove_dir_all(&dir);
+	}
 }
\ No newline at end of file
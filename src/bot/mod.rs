@@ -0,0 +1,5 @@
+pub mod assets;
+pub mod commands;
+pub mod config;
+pub mod permissions;
+pub mod worker;
@@ -0,0 +1,35 @@
+use serenity::model::Permissions;
+
+use super::config::Configuration;
+
+/// Message sent back to a member who lacks the permission required to run a
+/// gated command.
+pub const UNAUTHORIZED_MESSAGE: &str = "You don't have the required permission to do that.";
+
+/// Checks whether `member_permissions` satisfies the minimum permission
+/// configured for `command` on `config` (see `Configuration::get_required_permission`).
+/// A command with no configured requirement is always authorized.
+pub fn is_authorized(config: &Configuration, command: &str, member_permissions: Permissions) -> bool {
+	member_permissions.contains(config.get_required_permission(command))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_member_missing_the_required_permission() {
+		let mut config = Configuration::new();
+		config.set_required_permission("mute", Permissions::MUTE_MEMBERS);
+
+		assert!(!is_authorized(&config, "mute", Permissions::empty()));
+		assert!(!is_authorized(&config, "mute", Permissions::MANAGE_MESSAGES));
+		assert!(is_authorized(&config, "mute", Permissions::MUTE_MEMBERS));
+	}
+
+	#[test]
+	fn commands_without_a_configured_requirement_are_always_authorized() {
+		let config = Configuration::new();
+		assert!(is_authorized(&config, "some_unconfigured_command", Permissions::empty()));
+	}
+}
@@ -0,0 +1,308 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serenity::prelude::TypeMapKey;
+use tokio::sync::{mpsc, RwLock};
+
+/// Outcome of a single `Worker::work` call, telling the `WorkerManager` how
+/// to schedule the next poll.
+///
+/// Busy: 			there is more work to do right away, poll again immediately
+///
+/// Idle(delay): 	nothing to do for now, wait `delay` (or the manager's own
+/// 				configured delay if `None`) before polling again
+///
+/// Done: 			the worker is finished, it is marked dead and dropped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkerState {
+	Busy,
+	Idle(Option<Duration>),
+	Done,
+}
+
+/// Messages accepted by a worker's control channel, used to steer it from
+/// the outside (e.g. a command handler) without tearing down its task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkerControl {
+	Start,
+	Pause,
+	Resume,
+	Cancel,
+}
+
+/// A unit of background work driven in its own tokio task by the `WorkerManager`.
+#[async_trait]
+pub trait Worker: Send {
+	/// Human readable name, shown by the `$workers` command
+	fn name(&self) -> String;
+	/// Performs one step of work and reports what should happen next.
+	/// `WorkerManager` never cancels a `work()` call in progress — once
+	/// started it always runs to completion — so implementors don't need to
+	/// be cancellation-safe.
+	async fn work(&mut self) -> WorkerState;
+}
+
+/// Observed state of a worker, used to answer the `$workers` command without
+/// touching the worker itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkerStatus {
+	Active,
+	Idle,
+	Dead,
+}
+
+/// Snapshot of a single worker's identity and last known status.
+///
+/// `id` is an opaque, manager-assigned identity distinct from `name`: two
+/// workers (e.g. belonging to different guilds) can share the same display
+/// name, and `id` is what the manager actually matches on internally so
+/// their statuses can never cross.
+#[derive(Debug, Clone)]
+pub struct WorkerInfo {
+	id: u64,
+	name: String,
+	status: WorkerStatus,
+}
+
+impl WorkerInfo {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+	pub fn status(&self) -> WorkerStatus {
+		self.status
+	}
+}
+
+/// Updates the registry entry identified by `id` to `status`, if it's still there.
+async fn set_status(registry: &Arc<RwLock<Vec<WorkerInfo>>>, id: u64, status: WorkerStatus) {
+	let mut guard = registry.write().await;
+	if let Some(info) = guard.iter_mut().find(|info| info.id == id) {
+		info.status = status;
+	}
+}
+
+/// Spawns and supervises `Worker`s, each in its own tokio task, and keeps an
+/// observable registry of what is currently running.
+pub struct WorkerManager {
+	registry: Arc<RwLock<Vec<WorkerInfo>>>,
+	next_id: AtomicU64,
+}
+
+impl WorkerManager {
+	pub fn new() -> Self {
+		Self {
+			registry: Arc::new(RwLock::new(Vec::new())),
+			next_id: AtomicU64::new(0),
+		}
+	}
+
+	/// Spawns `worker` in its own task and drives it: `work()` is polled in a
+	/// loop, right away after `Busy` and, after `Idle`, once `default_delay`
+	/// (or the delay returned by `Idle(Some(_))`) has passed, until the worker
+	/// reports `Done` or is cancelled through the returned control channel.
+	pub async fn spawn<W: Worker + 'static>(&self, mut worker: W, default_delay: Duration) -> mpsc::Sender<WorkerControl> {
+		let (tx, mut rx) = mpsc::channel::<WorkerControl>(8);
+		let registry = Arc::clone(&self.registry);
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let name = worker.name();
+
+		{
+			let mut guard = registry.write().await;
+			guard.push(WorkerInfo {
+				id,
+				name: name.clone(),
+				status: WorkerStatus::Active,
+			});
+		}
+
+		tokio::spawn(async move {
+			let mut paused = false;
+
+			'drive: loop {
+				if paused {
+					match rx.recv().await {
+						Some(WorkerControl::Resume) | Some(WorkerControl::Start) => paused = false,
+						Some(WorkerControl::Cancel) | None => break 'drive,
+						Some(WorkerControl::Pause) => continue 'drive,
+					}
+					continue 'drive;
+				}
+
+				// Drain any pending control messages without racing `worker.work()`
+				// itself: `work()` offers no cancellation-safety guarantee, so once
+				// started it always runs to completion.
+				while let Ok(control) = rx.try_recv() {
+					match control {
+						WorkerControl::Pause => paused = true,
+						WorkerControl::Cancel => break 'drive,
+						WorkerControl::Start | WorkerControl::Resume => {}
+					}
+				}
+				if paused {
+					continue 'drive;
+				}
+
+				match worker.work().await {
+					WorkerState::Busy => {
+						set_status(&registry, id, WorkerStatus::Active).await;
+					}
+					WorkerState::Idle(delay) => {
+						set_status(&registry, id, WorkerStatus::Idle).await;
+
+						// Wait on the control channel and the delay together, so a
+						// `Pause`/`Cancel` sent while idle (e.g. poomp's "maintained"
+						// mode) is acted on immediately instead of only once the
+						// delay elapses.
+						tokio::select! {
+							control = rx.recv() => {
+								match control {
+									Some(WorkerControl::Pause) => paused = true,
+									Some(WorkerControl::Cancel) | None => break 'drive,
+									Some(WorkerControl::Start) | Some(WorkerControl::Resume) => {},
+								}
+							}
+							_ = tokio::time::sleep(delay.unwrap_or(default_delay)) => {}
+						}
+					}
+					WorkerState::Done => break 'drive,
+				}
+			}
+
+			set_status(&registry, id, WorkerStatus::Dead).await;
+		});
+
+		tx
+	}
+
+	/// Returns a snapshot of every worker this manager has ever spawned,
+	/// including dead ones, for the `$workers` command.
+	pub async fn status(&self) -> Vec<WorkerInfo> {
+		self.registry.read().await.clone()
+	}
+}
+
+impl Default for WorkerManager {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+pub struct WorkerManagerStore;
+
+impl TypeMapKey for WorkerManagerStore {
+	type Value = Arc<WorkerManager>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+	/// A `Worker` that plays back a fixed script of `WorkerState`s, one per
+	/// `work()` call, then reports `Done` forever after.
+	struct ScriptedWorker {
+		name: String,
+		calls: Arc<AtomicUsize>,
+		script: Vec<WorkerState>,
+	}
+
+	#[async_trait]
+	impl Worker for ScriptedWorker {
+		fn name(&self) -> String {
+			self.name.clone()
+		}
+		async fn work(&mut self) -> WorkerState {
+			let call = self.calls.fetch_add(1, AtomicOrdering::SeqCst);
+			self.script.get(call).copied().unwrap_or(WorkerState::Done)
+		}
+	}
+
+	#[tokio::test]
+	async fn reports_active_idle_and_dead_as_the_worker_progresses() {
+		let manager = WorkerManager::new();
+		let worker = ScriptedWorker {
+			name: String::from("scripted"),
+			calls: Arc::new(AtomicUsize::new(0)),
+			script: vec![WorkerState::Busy, WorkerState::Idle(Some(Duration::from_millis(20))), WorkerState::Done],
+		};
+
+		manager.spawn(worker, Duration::from_millis(20)).await;
+		assert_eq!(manager.status().await[0].status(), WorkerStatus::Active);
+
+		// `Busy` is re-polled at once, so by now the worker sits in its 20ms `Idle`.
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		assert_eq!(manager.status().await[0].status(), WorkerStatus::Idle);
+
+		tokio::time::sleep(Duration::from_millis(200)).await;
+		assert_eq!(manager.status().await[0].status(), WorkerStatus::Dead);
+	}
+
+	#[tokio::test]
+	async fn cancel_stops_an_idle_worker_before_its_delay_elapses() {
+		let manager = WorkerManager::new();
+		let worker = ScriptedWorker {
+			name: String::from("forever-idle"),
+			calls: Arc::new(AtomicUsize::new(0)),
+			script: vec![WorkerState::Idle(Some(Duration::from_secs(60)))],
+		};
+
+		let tx = manager.spawn(worker, Duration::from_secs(60)).await;
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		tx.send(WorkerControl::Cancel).await.unwrap();
+		tokio::time::sleep(Duration::from_millis(20)).await;
+
+		assert_eq!(manager.status().await[0].status(), WorkerStatus::Dead);
+	}
+
+	#[tokio::test]
+	async fn pause_stops_polling_until_resumed() {
+		let manager = WorkerManager::new();
+		let calls = Arc::new(AtomicUsize::new(0));
+		let worker = ScriptedWorker {
+			name: String::from("pausable"),
+			calls: Arc::clone(&calls),
+			script: vec![WorkerState::Idle(Some(Duration::from_millis(10))); 50],
+		};
+
+		let tx = manager.spawn(worker, Duration::from_millis(10)).await;
+		tokio::time::sleep(Duration::from_millis(15)).await;
+		tx.send(WorkerControl::Pause).await.unwrap();
+
+		tokio::time::sleep(Duration::from_millis(50)).await;
+		let calls_while_paused = calls.load(AtomicOrdering::SeqCst);
+		tokio::time::sleep(Duration::from_millis(50)).await;
+		assert_eq!(calls.load(AtomicOrdering::SeqCst), calls_while_paused, "no work should happen while paused");
+
+		tx.send(WorkerControl::Resume).await.unwrap();
+		tokio::time::sleep(Duration::from_millis(30)).await;
+		assert!(calls.load(AtomicOrdering::SeqCst) > calls_while_paused, "work should resume after Resume");
+
+		let _ = tx.send(WorkerControl::Cancel).await;
+	}
+
+	#[tokio::test]
+	async fn workers_with_the_same_name_get_independent_statuses() {
+		let manager = WorkerManager::new();
+		let first = ScriptedWorker {
+			name: String::from("flood"),
+			calls: Arc::new(AtomicUsize::new(0)),
+			script: vec![WorkerState::Done],
+		};
+		let second = ScriptedWorker {
+			name: String::from("flood"),
+			calls: Arc::new(AtomicUsize::new(0)),
+			script: vec![WorkerState::Idle(Some(Duration::from_secs(60)))],
+		};
+
+		manager.spawn(first, Duration::from_secs(60)).await;
+		manager.spawn(second, Duration::from_secs(60)).await;
+		tokio::time::sleep(Duration::from_millis(20)).await;
+
+		let statuses = manager.status().await;
+		assert_eq!(statuses.len(), 2);
+		assert_eq!(statuses.iter().filter(|info| info.status() == WorkerStatus::Dead).count(), 1);
+		assert_eq!(statuses.iter().filter(|info| info.status() == WorkerStatus::Idle).count(), 1);
+	}
+}